@@ -69,6 +69,27 @@
 //! TLS.with(|tls| assert_eq!(tls.get(), -1));
 //! ```
 //!
+//! A path may be followed by a parenthesized argument list, which is passed
+//! along to the macro it invokes, right before the item:
+//! ```
+//! use apply_macro::apply;
+//!
+//! macro_rules! add {
+//!     { ($lhs:expr, $rhs:expr) fn $name:ident() -> i32 { $body:expr } } => {
+//!         fn $name() -> i32 {
+//!             $body + $lhs + $rhs
+//!         }
+//!     };
+//! }
+//!
+//! #[apply(add(1, 2))]
+//! fn answer() -> i32 {
+//!     39
+//! }
+//!
+//! assert_eq!(answer(), 42);
+//! ```
+//!
 //! Empty argument is allowed (consistent with `#[derive()]`):
 //! ```
 //! use apply_macro::apply;
@@ -86,9 +107,76 @@
 //! # #[allow(dead_code)]
 //! struct Oops;
 //! ```
+//!
+//! [`apply_fn!`](apply_fn) is a function-like companion for use where an
+//! attribute can't be written, e.g. inside another macro's expansion:
+//! ```
+//! use apply_macro::apply_fn;
+//!
+//! macro_rules! in_mod {
+//!     ($input:item) => {
+//!         mod inner {
+//!             $input
+//!         }
+//!     };
+//! }
+//!
+//! apply_fn!(in_mod; pub fn answer() -> i32 { 42 });
+//!
+//! assert_eq!(inner::answer(), 42);
+//! ```
+//!
+//! For macros that transform the item rather than just adding to it (unlike
+//! the plain `derive`-style macros above), the order in which `#[apply(...)]`
+//! lists them matters. A leading `rev;` marker reverses it:
+//! ```
+//! use apply_macro::apply;
+//!
+//! macro_rules! neg {
+//!     { #[$attr:meta] fn $name:ident() -> i32 { $body:expr } } => {
+//!         #[$attr]
+//!         fn $name() -> i32 { -($body) }
+//!     };
+//!     { fn $name:ident() -> i32 { $body:expr } } => {
+//!         fn $name() -> i32 { -($body) }
+//!     };
+//! }
+//!
+//! macro_rules! dec {
+//!     { #[$attr:meta] fn $name:ident() -> i32 { $body:expr } } => {
+//!         #[$attr]
+//!         fn $name() -> i32 { ($body) - 1 }
+//!     };
+//!     { fn $name:ident() -> i32 { $body:expr } } => {
+//!         fn $name() -> i32 { ($body) - 1 }
+//!     };
+//! }
+//!
+//! #[apply(neg, dec)]
+//! fn a() -> i32 { 10 }
+//! assert_eq!(a(), -11); // dec(neg(10)) == (-10) - 1
+//!
+//! #[apply(rev; neg, dec)]
+//! fn b() -> i32 { 10 }
+//! assert_eq!(b(), -9); // neg(dec(10)) == -(10 - 1)
+//! ```
+//!
+//! `rev;` with no macros after it is a no-op, just like bare `#[apply()]`:
+//! ```
+//! use apply_macro::apply;
+//!
+//! #[apply(rev;)]
+//! #[derive(PartialEq, Debug)]
+//! struct NoOp;
+//!
+//! assert_eq!(NoOp, NoOp);
+//! ```
+
+extern crate alloc;
 
+use alloc::{string::ToString, vec::Vec};
 use core::iter::once;
-use proc_macro::{Delimiter, Group, Ident, Punct, Spacing, Span, TokenStream, TokenTree};
+use proc_macro::{Delimiter, Group, Ident, Literal, Punct, Spacing, Span, TokenStream, TokenTree};
 
 fn into_tt(tt: impl Into<TokenTree>) -> impl Iterator<Item = TokenTree> {
     once(tt.into())
@@ -104,12 +192,19 @@ macro_rules! punct {
 /// The main attribute macro of this crate.
 ///
 /// This macro accepts comma-separated paths to the function-like macros you
-/// want to call as arguments. See also [examples in the crate-level
-/// documentation](crate#example).
+/// want to call as arguments. A path may be followed by a parenthesized
+/// argument list (e.g. `foo(1, 2)`), which is forwarded to the invoked macro
+/// as a leading parenthesized group, right before the item. The first listed
+/// path is expanded outermost, and the last is expanded innermost; a leading
+/// `rev;` marker (e.g. `#[apply(rev; a, b, c)]`) reverses that order. See
+/// also [examples in the crate-level documentation](crate#examples).
 ///
-/// ## Limitation
-/// This macro does not validate its arguments:
-/// ```
+/// ## Malformed arguments
+/// If an entry isn't a plausible macro path, `apply` emits a
+/// `compile_error!` pointing at the offending token instead of silently
+/// generating a broken nested call. This covers an entry that starts with
+/// `#` (an attribute):
+/// ```compile_fail
 /// use apply_macro::apply;
 ///
 /// macro_rules! derive_debug {
@@ -121,49 +216,297 @@ macro_rules! punct {
 ///
 /// #[apply(#[derive(Debug)] struct AnotherStruct; derive_debug)]
 /// struct ImplsDebug;
+/// ```
+///
+/// a literal:
+/// ```compile_fail
+/// use apply_macro::apply;
+///
+/// macro_rules! derive_debug {
+///     ($input:item) => {
+///         #[derive(Debug)]
+///         $input
+///     };
+/// }
+///
+/// #[apply(1, derive_debug)]
+/// struct S;
+/// ```
+///
+/// `;`:
+/// ```compile_fail
+/// use apply_macro::apply;
+///
+/// macro_rules! derive_debug {
+///     ($input:item) => {
+///         #[derive(Debug)]
+///         $input
+///     };
+/// }
 ///
-/// dbg!(AnotherStruct, ImplsDebug);
+/// #[apply(; derive_debug)]
+/// struct S;
+/// ```
+///
+/// an empty entry, i.e. a stray `,`:
+/// ```compile_fail
+/// use apply_macro::apply;
+///
+/// macro_rules! derive_debug {
+///     ($input:item) => {
+///         #[derive(Debug)]
+///         $input
+///     };
+/// }
+///
+/// #[apply(, derive_debug)]
+/// struct S;
+/// ```
+///
+/// a delimited group other than `(...)`:
+/// ```compile_fail
+/// use apply_macro::apply;
+///
+/// macro_rules! derive_debug {
+///     ($input:item) => {
+///         #[derive(Debug)]
+///         $input
+///     };
+/// }
+///
+/// #[apply([0], derive_debug)]
+/// struct S;
+/// ```
+///
+/// more than one parenthesized argument list for the same entry:
+/// ```compile_fail
+/// use apply_macro::apply;
+///
+/// macro_rules! derive_debug {
+///     ($input:item) => {
+///         #[derive(Debug)]
+///         $input
+///     };
+/// }
+///
+/// #[apply(derive_debug(1)(2))]
+/// struct S;
+/// ```
+///
+/// and a parenthesized argument list with no preceding macro path:
+/// ```compile_fail
+/// use apply_macro::apply;
+///
+/// macro_rules! derive_debug {
+///     ($input:item) => {
+///         #[derive(Debug)]
+///         $input
+///     };
+/// }
+///
+/// #[apply((1, 2), derive_debug)]
+/// struct S;
 /// ```
 #[proc_macro_attribute]
 pub fn apply(args: TokenStream, input: TokenStream) -> TokenStream {
+    apply_to(args, input)
+}
+
+/// A function-like companion to [`apply`], for use where an attribute can't
+/// be written, e.g. inside another macro's expansion. It can't be named
+/// `apply!` because a proc-macro crate can't export two macros, of any
+/// kind, under the same name.
+///
+/// Syntax: the same comma-separated macro paths (with the same optional
+/// parenthesized arguments) as `apply`, then `;`, then the item. See also
+/// [an example in the crate-level documentation](crate#examples).
+#[proc_macro]
+pub fn apply_fn(input: TokenStream) -> TokenStream {
+    let mut iter = input.into_iter();
+    let mut args = TokenStream::new();
+    for tt in &mut iter {
+        if let TokenTree::Punct(ref punct) = tt {
+            if *punct == ';' {
+                return apply_to(args, iter.collect());
+            }
+        }
+        args.extend(once(tt));
+    }
+    compile_error(
+        "expected `;` separating the macro paths from the item",
+        Span::call_site(),
+        args,
+    )
+}
+
+fn apply_to(args: TokenStream, input: TokenStream) -> TokenStream {
     if args.is_empty() {
-        input
-    } else {
-        let mut args = args.into_iter();
-        let mut result = TokenStream::new();
-        for tt in &mut args {
-            if let TokenTree::Punct(ref punct) = tt {
-                if *punct == ',' {
-                    let args: TokenStream = args.collect();
-                    if args.is_empty() {
-                        break;
-                    }
-                    result.extend(
-                        punct!['!'].chain(into_tt(Group::new(
-                            Delimiter::Brace,
-                            punct!['#']
-                                .chain(into_tt(Group::new(
-                                    Delimiter::Bracket,
-                                    punct![':' ':']
-                                        .chain(into_tt(Ident::new(
-                                            "apply_macro",
-                                            Span::call_site(),
-                                        )))
-                                        .chain(punct![':' ':'])
-                                        .chain(into_tt(Ident::new("apply", Span::call_site())))
-                                        .chain(into_tt(Group::new(Delimiter::Parenthesis, args)))
-                                        .collect(),
-                                )))
-                                .chain(input)
-                                .collect(),
-                        ))),
+        return input;
+    }
+    let args = strip_rev_marker(args);
+    if args.is_empty() {
+        return input;
+    }
+    let mut args = args.into_iter().peekable();
+    let mut path = TokenStream::new();
+    let mut call_args = None;
+    while let Some(tt) = args.next() {
+        if path.is_empty() && call_args.is_none() {
+            if let Some(message) = malformed_entry_message(&tt) {
+                return compile_error(message, tt.span(), input);
+            }
+        }
+        match tt {
+            TokenTree::Punct(ref punct) if *punct == ',' => {
+                let rest: TokenStream = args.collect();
+                return emit(path, call_args, Some(rest), input);
+            }
+            TokenTree::Group(ref group) if group.delimiter() == Delimiter::Parenthesis => {
+                if call_args.is_none() && args.peek().is_none_or(is_comma) {
+                    call_args = Some(group.stream());
+                } else {
+                    return compile_error(
+                        "expected at most one parenthesized argument list, immediately before \
+                         this entry ends",
+                        group.span(),
+                        input,
                     );
-                    return result;
                 }
             }
-            result.extend(once(tt));
+            tt => path.extend(once(tt)),
+        }
+    }
+    emit(path, call_args, None, input)
+}
+
+fn is_comma(tt: &TokenTree) -> bool {
+    matches!(tt, TokenTree::Punct(punct) if *punct == ',')
+}
+
+/// If `args` starts with a `rev;` marker, consumes it and returns the
+/// remaining entries rebuilt in reversed order; otherwise returns `args`
+/// unchanged.
+fn strip_rev_marker(args: TokenStream) -> TokenStream {
+    let mut args = args.into_iter().peekable();
+    let is_rev_marker = matches!(args.peek(), Some(TokenTree::Ident(ident)) if ident.to_string() == "rev");
+    if !is_rev_marker {
+        return args.collect();
+    }
+    let rev = args.next().unwrap();
+    if matches!(args.peek(), Some(TokenTree::Punct(punct)) if *punct == ';') {
+        args.next();
+        reverse_entries(args.collect())
+    } else {
+        once(rev).chain(args).collect()
+    }
+}
+
+/// Splits `args` into comma-separated entries (allowing a trailing comma)
+/// and rejoins them in reversed order.
+fn reverse_entries(args: TokenStream) -> TokenStream {
+    let mut entries: Vec<TokenStream> = alloc::vec![TokenStream::new()];
+    for tt in args {
+        if let TokenTree::Punct(ref punct) = tt {
+            if *punct == ',' {
+                entries.push(TokenStream::new());
+                continue;
+            }
         }
-        result.extend(punct!['!'].chain(into_tt(Group::new(Delimiter::Brace, input))));
-        result
+        entries.last_mut().unwrap().extend(once(tt));
+    }
+    if entries.len() > 1 && entries.last().is_some_and(TokenStream::is_empty) {
+        entries.pop();
     }
+    entries.reverse();
+    let mut joined = TokenStream::new();
+    for (i, entry) in entries.into_iter().enumerate() {
+        if i > 0 {
+            joined.extend(punct![',']);
+        }
+        joined.extend(entry);
+    }
+    joined
+}
+
+/// Returns an error message if `tt` can't plausibly start a macro path, i.e.
+/// it's `#`, `;`, `,` (an empty entry), a literal, or a delimited group
+/// (parenthesized or otherwise).
+fn malformed_entry_message(tt: &TokenTree) -> Option<&'static str> {
+    match tt {
+        TokenTree::Punct(punct) if *punct == '#' => {
+            Some("expected a macro path, found `#`; attributes can't appear here")
+        }
+        TokenTree::Punct(punct) if *punct == ';' => Some("expected a macro path, found `;`"),
+        TokenTree::Punct(punct) if *punct == ',' => {
+            Some("expected a macro path, found `,`; empty entries aren't allowed")
+        }
+        TokenTree::Literal(_) => Some("expected a macro path, found a literal"),
+        TokenTree::Group(group) if group.delimiter() == Delimiter::Parenthesis => {
+            Some("expected a macro path before this parenthesized argument list")
+        }
+        TokenTree::Group(_) => {
+            Some("expected a macro path, found a delimited group that isn't `(...)`")
+        }
+        _ => None,
+    }
+}
+
+/// Builds a `compile_error!("message")` item, with its span pointing at the
+/// offending token, followed by the unmodified `input` so the item still
+/// parses.
+fn compile_error(message: &str, span: Span, input: TokenStream) -> TokenStream {
+    let mut message = Literal::string(message);
+    message.set_span(span);
+    into_tt(Ident::new("compile_error", span))
+        .chain(punct!['!'])
+        .chain(into_tt(Group::new(
+            Delimiter::Parenthesis,
+            into_tt(message).collect(),
+        )))
+        .chain(punct![';'])
+        .chain(input)
+        .collect()
+}
+
+/// Builds the `path! { (call_args)? body }` invocation for one entry of
+/// `apply`'s argument list, where `body` is either the item itself (if
+/// `rest` is `None` or empty, i.e. this is the last macro to apply) or the
+/// item wrapped in a self-referential `#[apply_macro::apply(rest)]`
+/// attribute that applies the remaining macros.
+fn emit(
+    path: TokenStream,
+    call_args: Option<TokenStream>,
+    rest: Option<TokenStream>,
+    input: TokenStream,
+) -> TokenStream {
+    let body = match rest {
+        Some(rest) if !rest.is_empty() => wrap_in_apply_attr(rest, input),
+        _ => input,
+    };
+    let mut brace_inner = TokenStream::new();
+    if let Some(call_args) = call_args {
+        brace_inner.extend(into_tt(Group::new(Delimiter::Parenthesis, call_args)));
+    }
+    brace_inner.extend(body);
+    path.into_iter()
+        .chain(punct!['!'])
+        .chain(into_tt(Group::new(Delimiter::Brace, brace_inner)))
+        .collect()
+}
+
+/// Wraps `input` in a `#[apply_macro::apply(args)]` attribute, so that the
+/// next expansion pass applies the remaining macros in `args`.
+fn wrap_in_apply_attr(args: TokenStream, input: TokenStream) -> TokenStream {
+    punct!['#']
+        .chain(into_tt(Group::new(
+            Delimiter::Bracket,
+            punct![':' ':']
+                .chain(into_tt(Ident::new("apply_macro", Span::call_site())))
+                .chain(punct![':' ':'])
+                .chain(into_tt(Ident::new("apply", Span::call_site())))
+                .chain(into_tt(Group::new(Delimiter::Parenthesis, args)))
+                .collect(),
+        )))
+        .chain(input)
+        .collect()
 }